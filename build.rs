@@ -0,0 +1,30 @@
+//! Compiles the Cap'n Proto and FlatBuffers schemas under `schemas/` into
+//! `OUT_DIR`, so `encoders::CapnpEncoder`/`FlatBuffersEncoder` can `include!`
+//! the generated bindings. Only runs when the `schema-backends` feature is
+//! enabled; it shells out to the `capnp`/`flatc` system binaries, which
+//! most machines won't have installed, so the default build skips it.
+
+fn main() {
+    #[cfg(feature = "schema-backends")]
+    compile_schemas();
+}
+
+#[cfg(feature = "schema-backends")]
+fn compile_schemas() {
+    println!("cargo:rerun-if-changed=schemas/tick.capnp");
+    println!("cargo:rerun-if-changed=schemas/tick.fbs");
+
+    capnpc::CompilerCommand::new()
+        .src_prefix("schemas")
+        .default_parent_module(vec!["encoders".into()])
+        .file("schemas/tick.capnp")
+        .run()
+        .expect("capnp schema compile failed - is the `capnp` binary installed?");
+
+    flatc_rust::run(flatc_rust::Args {
+        inputs: &[std::path::Path::new("schemas/tick.fbs")],
+        out_dir: std::path::Path::new(&std::env::var("OUT_DIR").unwrap()),
+        ..Default::default()
+    })
+    .expect("flatbuffers schema compile failed - is `flatc` installed?");
+}
@@ -0,0 +1,261 @@
+//! Pluggable wire-format backends for re-encoding a `Snapshot`, so the
+//! candidate formats can be benchmarked against each other (a "shootout")
+//! before picking one for archiving or piping TWSE snapshots into
+//! downstream systems.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::snapshot::{PriceLevel, Snapshot};
+
+/// A wire-format backend that can flatten a `Snapshot` into bytes.
+pub trait TickEncoder {
+    /// Short, human-readable name used in the shootout report.
+    fn name(&self) -> &'static str;
+
+    /// Append the encoded form of `snapshot` to `out`.
+    fn encode(&self, snapshot: &Snapshot, out: &mut Vec<u8>);
+}
+
+/// Days since the Unix epoch, used by the schema-based backends in place of
+/// a `NaiveDate` (none of them have a native date type in the flat schema).
+fn epoch_day(snapshot: &Snapshot) -> i32 {
+    let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid epoch date");
+    (snapshot.display_date - epoch).num_days() as i32
+}
+
+/// The same flat 7-field schema the other backends encode (security code,
+/// time, epoch day, price, volume, ten price levels) — used so the shootout
+/// compares equivalent payloads instead of pitting a full `Snapshot` against
+/// everyone else's cut-down schema.
+#[derive(Serialize)]
+struct FlatTick<'a> {
+    securities_code: &'a str,
+    display_time_ns: u64,
+    display_date_epoch_day: i32,
+    trade_price: i64,
+    transaction_volume: u64,
+    buy_levels: [PriceLevel; 5],
+    sell_levels: [PriceLevel; 5],
+}
+
+impl<'a> FlatTick<'a> {
+    fn from_snapshot(snapshot: &'a Snapshot) -> FlatTick<'a> {
+        FlatTick {
+            securities_code: &snapshot.securities_code,
+            display_time_ns: snapshot.display_time_ns,
+            display_date_epoch_day: epoch_day(snapshot),
+            trade_price: snapshot.trade_price,
+            transaction_volume: snapshot.transaction_volume,
+            buy_levels: snapshot.buy_levels,
+            sell_levels: snapshot.sell_levels,
+        }
+    }
+}
+
+pub struct BincodeEncoder;
+
+impl TickEncoder for BincodeEncoder {
+    fn name(&self) -> &'static str {
+        "bincode"
+    }
+
+    fn encode(&self, snapshot: &Snapshot, out: &mut Vec<u8>) {
+        bincode::serialize_into(out, &FlatTick::from_snapshot(snapshot)).expect("bincode encode");
+    }
+}
+
+/// Cap'n Proto backend. Schema: `schemas/tick.capnp`, compiled by `build.rs`
+/// into the `tick_capnp` module below. Only built with `--features
+/// schema-backends`, since it shells out to the `capnp` system binary.
+#[cfg(feature = "schema-backends")]
+pub struct CapnpEncoder;
+
+#[cfg(feature = "schema-backends")]
+impl TickEncoder for CapnpEncoder {
+    fn name(&self) -> &'static str {
+        "capnproto"
+    }
+
+    fn encode(&self, snapshot: &Snapshot, out: &mut Vec<u8>) {
+        let mut message = capnp::message::Builder::new_default();
+        {
+            let mut root = message.init_root::<tick_capnp::tick_snapshot::Builder>();
+            root.set_securities_code(&snapshot.securities_code);
+            root.set_display_time_ns(snapshot.display_time_ns);
+            root.set_display_date_epoch_day(epoch_day(snapshot));
+            root.set_trade_price(snapshot.trade_price);
+            root.set_transaction_volume(snapshot.transaction_volume);
+            fill_capnp_levels(root.reborrow().init_buy_levels(5), &snapshot.buy_levels);
+            fill_capnp_levels(root.reborrow().init_sell_levels(5), &snapshot.sell_levels);
+        }
+        capnp::serialize::write_message(out, &message).expect("capnp encode");
+    }
+}
+
+#[cfg(feature = "schema-backends")]
+fn fill_capnp_levels(
+    mut list: capnp::struct_list::Builder<tick_capnp::price_level::Owned>,
+    levels: &[PriceLevel; 5],
+) {
+    for (i, level) in levels.iter().enumerate() {
+        let mut entry = list.reborrow().get(i as u32);
+        entry.set_price(level.price);
+        entry.set_volume(level.volume);
+    }
+}
+
+#[cfg(feature = "schema-backends")]
+mod tick_capnp {
+    include!(concat!(env!("OUT_DIR"), "/tick_capnp.rs"));
+}
+
+/// FlatBuffers backend. Schema: `schemas/tick.fbs`, compiled by `build.rs`
+/// into the `tick_fbs` module below. Only built with `--features
+/// schema-backends`, since it shells out to the `flatc` system binary.
+#[cfg(feature = "schema-backends")]
+pub struct FlatBuffersEncoder;
+
+#[cfg(feature = "schema-backends")]
+impl TickEncoder for FlatBuffersEncoder {
+    fn name(&self) -> &'static str {
+        "flatbuffers"
+    }
+
+    fn encode(&self, snapshot: &Snapshot, out: &mut Vec<u8>) {
+        let mut builder = flatbuffers::FlatBufferBuilder::new();
+        let securities_code = builder.create_string(&snapshot.securities_code);
+        let buy_levels = build_fb_levels(&mut builder, &snapshot.buy_levels);
+        let sell_levels = build_fb_levels(&mut builder, &snapshot.sell_levels);
+        let root = tick_fbs::TickSnapshot::create(
+            &mut builder,
+            &tick_fbs::TickSnapshotArgs {
+                securities_code: Some(securities_code),
+                display_time_ns: snapshot.display_time_ns,
+                display_date_epoch_day: epoch_day(snapshot),
+                trade_price: snapshot.trade_price,
+                transaction_volume: snapshot.transaction_volume,
+                buy_levels: Some(buy_levels),
+                sell_levels: Some(sell_levels),
+            },
+        );
+        builder.finish(root, None);
+        out.extend_from_slice(builder.finished_data());
+    }
+}
+
+#[cfg(feature = "schema-backends")]
+fn build_fb_levels<'a>(
+    builder: &mut flatbuffers::FlatBufferBuilder<'a>,
+    levels: &[PriceLevel; 5],
+) -> flatbuffers::WIPOffset<flatbuffers::Vector<'a, tick_fbs::PriceLevel>> {
+    let structs: Vec<tick_fbs::PriceLevel> = levels
+        .iter()
+        .map(|l| tick_fbs::PriceLevel::new(l.price, l.volume))
+        .collect();
+    builder.create_vector(&structs)
+}
+
+#[cfg(feature = "schema-backends")]
+mod tick_fbs {
+    include!(concat!(env!("OUT_DIR"), "/tick_generated.rs"));
+    // `schemas/tick.fbs` declares `namespace twse.tick;`.
+    pub use self::twse::tick::*;
+}
+
+/// Hand-rolled Simple Binary Encoding (SBE)-style backend: a fixed-offset
+/// flat layout with no framing, carrying the same flat 7-field schema as
+/// `FlatTick` (the securities code truncated or space-padded to 6 bytes).
+/// Real SBE messages are normally generated from an XML spec
+/// via `sbe-tool`; there's no such Rust codegen in play here, so this lays
+/// the fields out by hand at the same fixed offsets a generated encoder
+/// would use.
+pub struct SbeEncoder;
+
+impl SbeEncoder {
+    // securities_code (6 bytes, space-padded) + display_time_ns (u64) +
+    // display_date_epoch_day (i32) + trade_price (i64) +
+    // transaction_volume (u64) + 5 buy levels + 5 sell levels (each
+    // price:i64 + volume:u64 = 16 bytes/level).
+    const RECORD_LEN: usize = 6 + 8 + 4 + 8 + 8 + 5 * 16 + 5 * 16;
+}
+
+impl TickEncoder for SbeEncoder {
+    fn name(&self) -> &'static str {
+        "sbe"
+    }
+
+    fn encode(&self, snapshot: &Snapshot, out: &mut Vec<u8>) {
+        let start = out.len();
+        out.resize(start + Self::RECORD_LEN, 0);
+        let buf = &mut out[start..];
+
+        let mut code_bytes = [b' '; 6];
+        let code = snapshot.securities_code.as_bytes();
+        let n = code.len().min(6);
+        code_bytes[..n].copy_from_slice(&code[..n]);
+        buf[0..6].copy_from_slice(&code_bytes);
+
+        buf[6..14].copy_from_slice(&snapshot.display_time_ns.to_le_bytes());
+        buf[14..18].copy_from_slice(&epoch_day(snapshot).to_le_bytes());
+        buf[18..26].copy_from_slice(&snapshot.trade_price.to_le_bytes());
+        buf[26..34].copy_from_slice(&snapshot.transaction_volume.to_le_bytes());
+
+        let mut offset = 34;
+        for level in snapshot.buy_levels.iter().chain(snapshot.sell_levels.iter()) {
+            buf[offset..offset + 8].copy_from_slice(&level.price.to_le_bytes());
+            buf[offset + 8..offset + 16].copy_from_slice(&level.volume.to_le_bytes());
+            offset += 16;
+        }
+    }
+}
+
+/// One row of the per-backend shootout report: total encoded bytes and
+/// elapsed time across all `records` snapshots.
+pub struct BackendReport {
+    pub name: &'static str,
+    pub total_bytes: usize,
+    pub records: usize,
+    pub elapsed: Duration,
+}
+
+impl BackendReport {
+    pub fn avg_bytes_per_record(&self) -> f64 {
+        self.total_bytes as f64 / self.records.max(1) as f64
+    }
+
+    pub fn records_per_sec(&self) -> f64 {
+        self.records as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Encode every snapshot with every backend and report bytes/throughput per
+/// backend.
+pub fn run_shootout(snapshots: &[Snapshot]) -> Vec<BackendReport> {
+    #[allow(unused_mut)]
+    let mut backends: Vec<Box<dyn TickEncoder>> =
+        vec![Box::new(BincodeEncoder), Box::new(SbeEncoder)];
+    #[cfg(feature = "schema-backends")]
+    {
+        backends.push(Box::new(CapnpEncoder));
+        backends.push(Box::new(FlatBuffersEncoder));
+    }
+
+    backends
+        .iter()
+        .map(|backend| {
+            let mut buf = Vec::new();
+            let start = Instant::now();
+            for snapshot in snapshots {
+                backend.encode(snapshot, &mut buf);
+            }
+            BackendReport {
+                name: backend.name(),
+                total_bytes: buf.len(),
+                records: snapshots.len(),
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,125 @@
+//! Compact binary re-encoding of parsed snapshots, as an alternative to the
+//! default `{:?}`-debug dump. Fixed-width ASCII TWSE files are huge and slow
+//! to re-read; re-encoding with integer-scaled prices and nanosecond
+//! timestamps (see `Snapshot`) gives much smaller files and far faster
+//! subsequent loads.
+
+use std::io::{self, Read, Write};
+
+use crate::snapshot::Snapshot;
+
+/// How a `Snapshot` should be written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    /// `{:?}`-debug print, one snapshot per line (the original behavior).
+    Debug,
+    Bincode,
+    Postcard,
+}
+
+impl OutputMode {
+    /// Parse `--format=<debug|bincode|postcard>` out of the process
+    /// arguments, defaulting to `Debug` if absent or unrecognized.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> OutputMode {
+        for arg in args {
+            if let Some(format) = arg.strip_prefix("--format=") {
+                match format {
+                    "bincode" => return OutputMode::Bincode,
+                    "postcard" => return OutputMode::Postcard,
+                    _ => return OutputMode::Debug,
+                }
+            }
+        }
+        OutputMode::Debug
+    }
+}
+
+/// Serialize `snapshot` with `mode` and write it length-delimited (a u32 LE
+/// byte length, then the payload) to `writer`. No-op for `OutputMode::Debug`.
+pub fn write_record<W: Write>(writer: &mut W, mode: OutputMode, snapshot: &Snapshot) -> io::Result<()> {
+    let payload = match mode {
+        OutputMode::Debug => return Ok(()),
+        OutputMode::Bincode => bincode::serialize(snapshot)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+        OutputMode::Postcard => postcard::to_allocvec(snapshot)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+    };
+    writer.write_all(&(payload.len() as u32).to_le_bytes())?;
+    writer.write_all(&payload)?;
+    Ok(())
+}
+
+/// Read one length-delimited record written by `write_record` and decode it
+/// back into a `Snapshot`. Returns `Ok(None)` at a clean end-of-stream.
+#[allow(dead_code)]
+pub fn read_record<R: Read>(reader: &mut R, mode: OutputMode) -> io::Result<Option<Snapshot>> {
+    let mut len_buf = [0u8; 4];
+    match reader.read_exact(&mut len_buf) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let len = u32::from_le_bytes(len_buf) as usize;
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    let snapshot = match mode {
+        OutputMode::Debug => return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "OutputMode::Debug has no binary encoding to decode",
+        )),
+        OutputMode::Bincode => bincode::deserialize(&payload)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+        OutputMode::Postcard => postcard::from_bytes(&payload)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+    };
+    Ok(Some(snapshot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::{PriceLevel, Snapshot, PRICE_SCALE_4};
+    use chrono::NaiveDate;
+
+    fn sample_snapshot() -> Snapshot {
+        Snapshot {
+            securities_code: "2330".to_string(),
+            display_time_ns: 12 * 3_600_000_000_000,
+            display_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            remark: ' ',
+            trend_flag: '+',
+            match_flag: 'Y',
+            trade_upper_lower_limit: ' ',
+            trade_price: 5_850_000,
+            transaction_volume: 1_000,
+            price_scale: PRICE_SCALE_4,
+            buy_tick_size: '5',
+            buy_upper_lower_limit: ' ',
+            buy_levels: [PriceLevel { price: 5_849_000, volume: 10 }; 5],
+            sell_tick_size: '5',
+            sell_upper_lower_limit: ' ',
+            sell_levels: [PriceLevel { price: 5_851_000, volume: 20 }; 5],
+            match_staff: "01".to_string(),
+        }
+    }
+
+    fn round_trips(mode: OutputMode) {
+        let snapshot = sample_snapshot();
+        let mut buf = Vec::new();
+        write_record(&mut buf, mode, &snapshot).expect("write_record");
+        let decoded = read_record(&mut buf.as_slice(), mode)
+            .expect("read_record")
+            .expect("one record");
+        assert_eq!(decoded, snapshot);
+    }
+
+    #[test]
+    fn bincode_round_trips_exactly() {
+        round_trips(OutputMode::Bincode);
+    }
+
+    #[test]
+    fn postcard_round_trips_exactly() {
+        round_trips(OutputMode::Postcard);
+    }
+}
@@ -1,166 +1,144 @@
 use std::fs::File;
-use std::io::{BufRead, BufReader, Error};
+use std::io::{self, Error, Write};
 
-/// The "new" 190-byte snapshot format (after 2020/03/01).
-/// Byte offsets (1-indexed as per your doc):
-///   1-6   => securities_code
-///   7-18  => display_time
-///   19    => remark
-///   20    => trend_flag
-///   21    => match_flag
-///   22    => trade_upper_lower_limit
-///   23-28 => trade_price
-///   29-36 => transaction_volume
-///   37    => buy_tick_size
-///   38    => buy_upper_lower_limit
-///   39-108  => buy_5_price_volume
-///   109    => sell_tick_size
-///   110    => sell_upper_lower_limit
-///   111-180 => sell_5_price_volume
-///   181-188 => display_date
-///   189-190 => match_staff
-#[allow(dead_code)]
-#[derive(Debug)]
-struct TwseSnapshotNew {
-    securities_code: String,
-    display_time: String,
-    remark: char,
-    trend_flag: char,
-    match_flag: char,
-    trade_upper_lower_limit: char,
-    trade_price: String,
-    transaction_volume: String,
-    buy_tick_size: char,
-    buy_upper_lower_limit: char,
-    buy_5_price_volume: String,
-    sell_tick_size: char,
-    sell_upper_lower_limit: char,
-    sell_5_price_volume: String,
-    display_date: String,
-    match_staff: String,
-}
+mod encoders;
+mod encoding;
+mod reader;
+mod snapshot;
+mod summarizer;
 
-/// The "old" 186-byte snapshot format (before 2020/03/01).
-/// Byte offsets (1-indexed):
-///   1-6   => securities_code
-///   7-14  => display_time
-///   15    => remark
-///   16    => trend_flag
-///   17    => match_flag
-///   18    => trade_upper_lower_limit
-///   19-24 => trade_price
-///   25-32 => transaction_volume
-///   33    => buy_tick_size
-///   34    => buy_upper_lower_limit
-///   35-104  => buy_5_price_volume
-///   105    => sell_tick_size
-///   106    => sell_upper_lower_limit
-///   107-176 => sell_5_price_volume
-///   177-184 => display_date
-///   185-186 => match_staff
-#[allow(dead_code)]
-#[derive(Debug)]
-struct TwseSnapshotOld {
-    securities_code: String,
-    display_time: String,
-    remark: char,
-    trend_flag: char,
-    match_flag: char,
-    trade_upper_lower_limit: char,
-    trade_price: String,
-    transaction_volume: String,
-    buy_tick_size: char,
-    buy_upper_lower_limit: char,
-    buy_5_price_volume: String,
-    sell_tick_size: char,
-    sell_upper_lower_limit: char,
-    sell_5_price_volume: String,
-    display_date: String,
-    match_staff: String,
-}
+use encoding::OutputMode;
+use reader::RecordReader;
+use snapshot::{parse_new_format, parse_old_format, Snapshot, PRICE_SCALE_2, PRICE_SCALE_4};
+use summarizer::Summarizer;
 
-/// Parse a 190-byte line into TwseSnapshotNew
-fn parse_new_format(line: &str) -> TwseSnapshotNew {
-    TwseSnapshotNew {
-        securities_code:         line[0..6].trim().to_string(),
-        display_time:            line[6..18].trim().to_string(),
-        remark:                  line.chars().nth(18).unwrap_or(' '),
-        trend_flag:              line.chars().nth(19).unwrap_or(' '),
-        match_flag:              line.chars().nth(20).unwrap_or(' '),
-        trade_upper_lower_limit: line.chars().nth(21).unwrap_or(' '),
-        trade_price:             line[22..28].trim().to_string(),
-        transaction_volume:      line[28..36].trim().to_string(),
-        buy_tick_size:           line.chars().nth(36).unwrap_or(' '),
-        buy_upper_lower_limit:   line.chars().nth(37).unwrap_or(' '),
-        buy_5_price_volume:      line[38..108].trim().to_string(),
-        sell_tick_size:          line.chars().nth(108).unwrap_or(' '),
-        sell_upper_lower_limit:  line.chars().nth(109).unwrap_or(' '),
-        sell_5_price_volume:     line[110..180].trim().to_string(),
-        display_date:            line[180..188].trim().to_string(),
-        match_staff:             line[188..190].trim().to_string(),
+/// Parse `--price-scale=<2|4>` out of the process arguments, defaulting to
+/// `PRICE_SCALE_2` (most securities use 2 implied decimal places) if absent
+/// or unrecognized.
+fn parse_price_scale(args: &[String]) -> i64 {
+    for arg in args {
+        if let Some(scale) = arg.strip_prefix("--price-scale=") {
+            match scale {
+                "2" => return PRICE_SCALE_2,
+                "4" => return PRICE_SCALE_4,
+                _ => return PRICE_SCALE_2,
+            }
+        }
     }
+    PRICE_SCALE_2
 }
 
-/// Parse a 186-byte line into TwseSnapshotOld
-fn parse_old_format(line: &str) -> TwseSnapshotOld {
-    TwseSnapshotOld {
-        securities_code:         line[0..6].trim().to_string(),
-        display_time:            line[6..14].trim().to_string(),
-        remark:                  line.chars().nth(14).unwrap_or(' '),
-        trend_flag:              line.chars().nth(15).unwrap_or(' '),
-        match_flag:              line.chars().nth(16).unwrap_or(' '),
-        trade_upper_lower_limit: line.chars().nth(17).unwrap_or(' '),
-        trade_price:             line[18..24].trim().to_string(),
-        transaction_volume:      line[24..32].trim().to_string(),
-        buy_tick_size:           line.chars().nth(32).unwrap_or(' '),
-        buy_upper_lower_limit:   line.chars().nth(33).unwrap_or(' '),
-        buy_5_price_volume:      line[34..104].trim().to_string(),
-        sell_tick_size:          line.chars().nth(104).unwrap_or(' '),
-        sell_upper_lower_limit:  line.chars().nth(105).unwrap_or(' '),
-        sell_5_price_volume:     line[106..176].trim().to_string(),
-        display_date:            line[176..184].trim().to_string(),
-        match_staff:             line[184..186].trim().to_string(),
+/// Render a `session_high`/`session_low` that's `None` (no executed trade
+/// yet, e.g. a quote-only security) as `"-"` instead of printing nothing.
+fn format_opt_price(price: Option<i64>) -> String {
+    match price {
+        Some(p) => p.to_string(),
+        None => "-".to_string(),
     }
 }
 
 fn main() -> Result<(), Error> {
+    let args: Vec<String> = std::env::args().collect();
+    // --shootout encodes every parsed snapshot with each TickEncoder backend
+    // and reports bytes/throughput instead of the usual per-line output.
+    let shootout = args.iter().any(|a| a == "--shootout");
+    // --summary folds the snapshot stream into a per-security end-of-day
+    // summary instead of the usual per-line output.
+    let summary = args.iter().any(|a| a == "--summary");
+    // --price-scale=<2|4> selects the implied decimal places of trade/level
+    // prices (most securities use 2; some, e.g. odd-lot issues, use 4).
+    let price_scale = parse_price_scale(&args);
+    // --format=<debug|bincode|postcard> selects how parsed snapshots are
+    // emitted; defaults to the original `{:?}`-debug dump.
+    let mode = OutputMode::from_args(args);
+
     // Open the file (replace with your actual file path).
-    // Each line in the file should be either 190 or 186 characters (no trailing newline).
+    // Each record in the file should be either 190 or 186 bytes before the
+    // `\n` (or `\r\n`) terminator.
     let file = File::open("snapshot/Sample")?;
     // let mut file = File::open("snapshot/Sample_new")?;
-    let reader = BufReader::new(file);
+    let mut records = RecordReader::new(file);
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut shootout_snapshots: Vec<Snapshot> = Vec::new();
+    let mut summarizer = Summarizer::new();
 
-    for (i, line_result) in reader.lines().enumerate() {
-        let line_raw = line_result?;
-        // Trim end to remove any trailing newline or carriage return
-        let line = line_raw.trim_end();
+    let mut i = 0usize;
+    while let Some(line) = records.next_record()? {
+        i += 1;
 
         if line.is_empty() {
             // Possibly skip empty lines
             continue;
         }
 
-        match line.len() {
-            190 => {
-                // Parse new format
-                let snapshot = parse_new_format(line);
-                println!("Line {} => New format => {:?}", i + 1, snapshot);
-            },
-            186 => {
-                // Parse old format
-                let snapshot = parse_old_format(line);
-                println!("Line {} => Old format => {:?}", i + 1, snapshot);
-            },
+        let snapshot = match line.len() {
+            190 => parse_new_format(line, price_scale),
+            186 => parse_old_format(line, price_scale),
             other => {
                 eprintln!(
                     "Line {} => Unexpected length {}. Skipping: {}",
-                    i + 1,
+                    i,
                     other,
-                    line
+                    String::from_utf8_lossy(line)
                 );
+                continue;
+            }
+        };
+
+        if shootout {
+            shootout_snapshots.push(snapshot);
+            continue;
+        }
+
+        if summary {
+            summarizer.update(&snapshot);
+            continue;
+        }
+
+        match mode {
+            OutputMode::Debug => println!("Line {} => {:?}", i, snapshot),
+            OutputMode::Bincode | OutputMode::Postcard => {
+                encoding::write_record(&mut out, mode, &snapshot)?;
             }
         }
     }
+    out.flush()?;
+
+    if shootout {
+        println!("{:<12} {:>12} {:>16} {:>14}", "backend", "bytes", "bytes/record", "records/sec");
+        for report in encoders::run_shootout(&shootout_snapshots) {
+            println!(
+                "{:<12} {:>12} {:>16.1} {:>14.0}",
+                report.name,
+                report.total_bytes,
+                report.avg_bytes_per_record(),
+                report.records_per_sec(),
+            );
+        }
+    }
+
+    if summary {
+        println!(
+            "{:<8} {:>10} {:>10} {:>10} {:>14} {:>12} {:>10} {:>10} {:>10} {:>10}",
+            "code", "last", "high", "low", "vwap", "cum_vol", "bid", "bid_vol", "ask", "ask_vol"
+        );
+        for (code, s) in summarizer.summaries() {
+            println!(
+                "{:<8} {:>10} {:>10} {:>10} {:>14.4} {:>12} {:>10} {:>10} {:>10} {:>10}",
+                code,
+                s.last_trade_price,
+                format_opt_price(s.session_high),
+                format_opt_price(s.session_low),
+                s.vwap(),
+                s.cumulative_volume,
+                s.best_bid,
+                s.best_bid_volume,
+                s.best_ask,
+                s.best_ask_volume,
+            );
+        }
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
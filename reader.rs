@@ -0,0 +1,129 @@
+//! Byte-oriented record reader for fixed-width TWSE snapshot files.
+//!
+//! `std::io::BufRead::lines()` validates every line as UTF-8 and allocates a
+//! fresh `String` per line, and `line.chars().nth(i)` re-scans from the start
+//! of the line for every field access. Since every TWSE field is ASCII at a
+//! known byte offset, `RecordReader` instead reads raw bytes into a reused
+//! buffer and hands back each record as a `&[u8]` slice into it, so callers
+//! can parse digits directly from bytes with no UTF-8 validation and no
+//! per-record allocation.
+
+use std::io::{self, Read};
+
+/// Find the first occurrence of `needle` in `haystack`.
+fn find_byte(haystack: &[u8], needle: u8) -> Option<usize> {
+    haystack.iter().position(|&b| b == needle)
+}
+
+/// Reads `\n`-terminated fixed-width records out of `inner` into a single
+/// reused buffer, growing it only if a record (plus terminator) doesn't fit.
+pub struct RecordReader<R> {
+    inner: R,
+    buf: Vec<u8>,
+    start: usize,
+    end: usize,
+}
+
+impl<R: Read> RecordReader<R> {
+    pub fn new(inner: R) -> Self {
+        RecordReader {
+            inner,
+            buf: vec![0u8; 64 * 1024],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// Return the next record's bytes, with the `\n` (and a preceding `\r`,
+    /// if any) stripped. Returns `Ok(None)` at a clean end-of-file. The
+    /// returned slice borrows the reader's internal buffer and is only
+    /// valid until the next call to `next_record`.
+    pub fn next_record(&mut self) -> io::Result<Option<&[u8]>> {
+        loop {
+            if let Some(nl) = find_byte(&self.buf[self.start..self.end], b'\n') {
+                let line_end = self.start + nl;
+                let mut record_end = line_end;
+                if record_end > self.start && self.buf[record_end - 1] == b'\r' {
+                    record_end -= 1;
+                }
+                let record_start = self.start;
+                self.start = line_end + 1;
+                return Ok(Some(&self.buf[record_start..record_end]));
+            }
+
+            // No terminator in what we've buffered: compact the unconsumed
+            // tail to the front, grow if we're out of room, and read more.
+            if self.start > 0 {
+                self.buf.copy_within(self.start..self.end, 0);
+                self.end -= self.start;
+                self.start = 0;
+            }
+            if self.end == self.buf.len() {
+                self.buf.resize(self.buf.len() * 2, 0);
+            }
+
+            let n = self.inner.read(&mut self.buf[self.end..])?;
+            if n == 0 {
+                // EOF: hand back whatever's left as a final, unterminated record.
+                return Ok(if self.start < self.end {
+                    let record = self.start..self.end;
+                    self.start = self.end;
+                    Some(&self.buf[record])
+                } else {
+                    None
+                });
+            }
+            self.end += n;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn records_of(input: &[u8]) -> Vec<Vec<u8>> {
+        let mut reader = RecordReader::new(Cursor::new(input.to_vec()));
+        let mut records = Vec::new();
+        while let Some(record) = reader.next_record().unwrap() {
+            records.push(record.to_vec());
+        }
+        records
+    }
+
+    #[test]
+    fn splits_on_lf() {
+        assert_eq!(records_of(b"abc\ndef\n"), vec![b"abc".to_vec(), b"def".to_vec()]);
+    }
+
+    #[test]
+    fn strips_trailing_cr() {
+        assert_eq!(records_of(b"abc\r\ndef\r\n"), vec![b"abc".to_vec(), b"def".to_vec()]);
+    }
+
+    #[test]
+    fn returns_final_unterminated_record_at_eof() {
+        assert_eq!(records_of(b"abc\ndef"), vec![b"abc".to_vec(), b"def".to_vec()]);
+    }
+
+    #[test]
+    fn empty_input_yields_no_records() {
+        assert_eq!(records_of(b""), Vec::<Vec<u8>>::new());
+    }
+
+    #[test]
+    fn empty_lines_yield_empty_records() {
+        assert_eq!(records_of(b"\n\n"), vec![Vec::new(), Vec::new()]);
+    }
+
+    #[test]
+    fn grows_the_buffer_for_a_record_larger_than_the_initial_capacity() {
+        let long_record = vec![b'x'; 128 * 1024];
+        let mut input = long_record.clone();
+        input.push(b'\n');
+        input.extend_from_slice(b"short\n");
+
+        assert_eq!(records_of(&input), vec![long_record, b"short".to_vec()]);
+    }
+}
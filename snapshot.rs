@@ -0,0 +1,385 @@
+//! Typed, format-independent representation of a TWSE tick snapshot, plus
+//! the parsers that decode it from the 186-byte (old) and 190-byte (new)
+//! fixed-width wire formats.
+//!
+//! Every field in both formats is ASCII at a known byte offset, so the
+//! parsers below work directly on `&[u8]` records (see `reader::RecordReader`)
+//! rather than validated `&str` lines, avoiding per-line UTF-8 validation.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// TWSE price fields are fixed-width integers with an implied decimal point;
+/// divide a parsed price by the snapshot's `price_scale` to recover the
+/// decimal value. Most securities use 2 implied decimal places, but some
+/// (e.g. odd-lot/emerging board issues) use 4; callers pick the scale that
+/// matches the security being parsed and pass it to `parse_new_format`/
+/// `parse_old_format`.
+pub const PRICE_SCALE_2: i64 = 100;
+pub const PRICE_SCALE_4: i64 = 10_000;
+
+/// A single level of the 5-level best-bid/ask book: an integer-scaled price
+/// (see `Snapshot::price_scale`) and the volume resting at that price.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceLevel {
+    pub price: i64,
+    pub volume: u64,
+}
+
+/// Unified, typed representation of a TWSE tick snapshot, independent of
+/// whether it came from the 186-byte (old) or 190-byte (new) wire format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub securities_code: String,
+    /// Nanoseconds since midnight, decoded from the `display_time` field
+    /// (`HHMMSSffffff` in the new format, `HHMMSSff` in the old one).
+    pub display_time_ns: u64,
+    /// Civil date decoded from the `YYYYMMDD` `display_date` field.
+    pub display_date: NaiveDate,
+    pub remark: char,
+    pub trend_flag: char,
+    pub match_flag: char,
+    pub trade_upper_lower_limit: char,
+    /// Integer-scaled trade price; divide by `price_scale` for the decimal price.
+    pub trade_price: i64,
+    pub transaction_volume: u64,
+    /// Divisor that recovers the decimal price from `trade_price` and the
+    /// prices in `buy_levels`/`sell_levels` (see `PRICE_SCALE_2`/`PRICE_SCALE_4`).
+    pub price_scale: i64,
+    pub buy_tick_size: char,
+    pub buy_upper_lower_limit: char,
+    pub buy_levels: [PriceLevel; 5],
+    pub sell_tick_size: char,
+    pub sell_upper_lower_limit: char,
+    pub sell_levels: [PriceLevel; 5],
+    pub match_staff: String,
+}
+
+/// Trim ASCII spaces from both ends of a byte slice.
+fn trim_ascii(bytes: &[u8]) -> &[u8] {
+    let mut start = 0;
+    let mut end = bytes.len();
+    while start < end && bytes[start] == b' ' {
+        start += 1;
+    }
+    while end > start && bytes[end - 1] == b' ' {
+        end -= 1;
+    }
+    &bytes[start..end]
+}
+
+/// Parse an ASCII digit field directly from bytes, ignoring anything that
+/// isn't a digit; a blank field yields 0.
+fn parse_digits(bytes: &[u8]) -> u64 {
+    trim_ascii(bytes).iter().fold(0u64, |acc, &b| {
+        if b.is_ascii_digit() {
+            acc * 10 + (b - b'0') as u64
+        } else {
+            acc
+        }
+    })
+}
+
+/// Decode an ASCII byte as the `char` it represents, TWSE fields being
+/// single-byte flags.
+fn byte_char(bytes: &[u8], index: usize) -> char {
+    bytes.get(index).copied().unwrap_or(b' ') as char
+}
+
+/// Copy a trimmed ASCII byte range out as an owned `String`.
+fn byte_str(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(trim_ascii(bytes)).into_owned()
+}
+
+/// Parse a 70-byte `buy_5_price_volume`/`sell_5_price_volume` block into five
+/// fixed-width levels (6-digit price + 8-digit volume each). `tick_size` is
+/// the number of levels TWSE actually populated; any levels beyond it are
+/// left zeroed.
+fn parse_price_levels(block: &[u8], tick_size: u8) -> [PriceLevel; 5] {
+    let mut levels = [PriceLevel::default(); 5];
+    for (i, level) in levels.iter_mut().enumerate() {
+        if i as u8 >= tick_size {
+            break;
+        }
+        let start = i * 14;
+        let price = block.get(start..start + 6).unwrap_or(&[]);
+        let volume = block.get(start + 6..start + 14).unwrap_or(&[]);
+        level.price = parse_digits(price) as i64;
+        level.volume = parse_digits(volume);
+    }
+    levels
+}
+
+/// Convert a `buy_tick_size`/`sell_tick_size` digit char into the number of
+/// populated levels (0-5).
+fn tick_size_to_count(c: char) -> u8 {
+    c.to_digit(10).unwrap_or(0) as u8
+}
+
+/// Decode a TWSE `display_time` field (`HHMMSSffffff` for the new format,
+/// `HHMMSSff` for the old one) into nanoseconds since midnight.
+fn parse_display_time_ns(raw: &[u8]) -> u64 {
+    let raw = trim_ascii(raw);
+    let digits: Vec<u32> = raw
+        .iter()
+        .filter(|b| b.is_ascii_digit())
+        .map(|b| (b - b'0') as u32)
+        .collect();
+    if digits.len() < 6 {
+        return 0;
+    }
+    let hh = digits[0] * 10 + digits[1];
+    let mm = digits[2] * 10 + digits[3];
+    let ss = digits[4] * 10 + digits[5];
+    // The remaining digits are fractional seconds: 6 digits (microseconds)
+    // for the new format, 2 digits (centiseconds) for the old one.
+    let frac_digits = &digits[6..];
+    let frac_value = frac_digits.iter().fold(0u64, |acc, d| acc * 10 + *d as u64);
+    let frac_ns = frac_value * 10u64.pow(9 - frac_digits.len() as u32);
+    let seconds_ns = (hh as u64 * 3600 + mm as u64 * 60 + ss as u64) * 1_000_000_000;
+    seconds_ns + frac_ns
+}
+
+/// Decode a TWSE `display_date` field (`YYYYMMDD`) into a civil date.
+fn parse_display_date(raw: &[u8]) -> NaiveDate {
+    let raw = trim_ascii(raw);
+    let year = parse_digits(raw.get(0..4).unwrap_or(b"1970")) as i32;
+    let month = parse_digits(raw.get(4..6).unwrap_or(b"1")) as u32;
+    let day = parse_digits(raw.get(6..8).unwrap_or(b"1")) as u32;
+    NaiveDate::from_ymd_opt(year, month.max(1), day.max(1)).unwrap_or(NaiveDate::MIN)
+}
+
+/// Parse a 190-byte record (the "new" format, used after 2020/03/01) into a
+/// `Snapshot`. `line` must not include the line terminator.
+/// Byte offsets (1-indexed as per your doc):
+///   1-6   => securities_code
+///   7-18  => display_time
+///   19    => remark
+///   20    => trend_flag
+///   21    => match_flag
+///   22    => trade_upper_lower_limit
+///   23-28 => trade_price
+///   29-36 => transaction_volume
+///   37    => buy_tick_size
+///   38    => buy_upper_lower_limit
+///   39-108  => buy_5_price_volume
+///   109    => sell_tick_size
+///   110    => sell_upper_lower_limit
+///   111-180 => sell_5_price_volume
+///   181-188 => display_date
+///   189-190 => match_staff
+pub fn parse_new_format(line: &[u8], price_scale: i64) -> Snapshot {
+    let buy_tick_size = byte_char(line, 36);
+    let sell_tick_size = byte_char(line, 108);
+    Snapshot {
+        securities_code:         byte_str(&line[0..6]),
+        display_time_ns:         parse_display_time_ns(&line[6..18]),
+        display_date:            parse_display_date(&line[180..188]),
+        remark:                  byte_char(line, 18),
+        trend_flag:              byte_char(line, 19),
+        match_flag:              byte_char(line, 20),
+        trade_upper_lower_limit: byte_char(line, 21),
+        trade_price:             parse_digits(&line[22..28]) as i64,
+        transaction_volume:      parse_digits(&line[28..36]),
+        price_scale,
+        buy_tick_size,
+        buy_upper_lower_limit:   byte_char(line, 37),
+        buy_levels:              parse_price_levels(&line[38..108], tick_size_to_count(buy_tick_size)),
+        sell_tick_size,
+        sell_upper_lower_limit:  byte_char(line, 109),
+        sell_levels:             parse_price_levels(&line[110..180], tick_size_to_count(sell_tick_size)),
+        match_staff:             byte_str(&line[188..190]),
+    }
+}
+
+/// Parse a 186-byte record (the "old" format, used before 2020/03/01) into a
+/// `Snapshot`. `line` must not include the line terminator.
+/// Byte offsets (1-indexed):
+///   1-6   => securities_code
+///   7-14  => display_time
+///   15    => remark
+///   16    => trend_flag
+///   17    => match_flag
+///   18    => trade_upper_lower_limit
+///   19-24 => trade_price
+///   25-32 => transaction_volume
+///   33    => buy_tick_size
+///   34    => buy_upper_lower_limit
+///   35-104  => buy_5_price_volume
+///   105    => sell_tick_size
+///   106    => sell_upper_lower_limit
+///   107-176 => sell_5_price_volume
+///   177-184 => display_date
+///   185-186 => match_staff
+pub fn parse_old_format(line: &[u8], price_scale: i64) -> Snapshot {
+    let buy_tick_size = byte_char(line, 32);
+    let sell_tick_size = byte_char(line, 104);
+    Snapshot {
+        securities_code:         byte_str(&line[0..6]),
+        display_time_ns:         parse_display_time_ns(&line[6..14]),
+        display_date:            parse_display_date(&line[176..184]),
+        remark:                  byte_char(line, 14),
+        trend_flag:              byte_char(line, 15),
+        match_flag:              byte_char(line, 16),
+        trade_upper_lower_limit: byte_char(line, 17),
+        trade_price:             parse_digits(&line[18..24]) as i64,
+        transaction_volume:      parse_digits(&line[24..32]),
+        price_scale,
+        buy_tick_size,
+        buy_upper_lower_limit:   byte_char(line, 33),
+        buy_levels:              parse_price_levels(&line[34..104], tick_size_to_count(buy_tick_size)),
+        sell_tick_size,
+        sell_upper_lower_limit:  byte_char(line, 105),
+        sell_levels:             parse_price_levels(&line[106..176], tick_size_to_count(sell_tick_size)),
+        match_staff:             byte_str(&line[184..186]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One level: 6-digit price, 8-digit volume.
+    fn level_bytes(price: &str, volume: &str) -> String {
+        format!("{:0>6}{:0>8}", price, volume)
+    }
+
+    /// 70-byte `buy_5_price_volume`/`sell_5_price_volume` block: 5 levels
+    /// priced `base_price + i` with volume `base_volume + i`.
+    fn levels_block(base_price: i64, base_volume: u64) -> String {
+        (0..5)
+            .map(|i| level_bytes(&(base_price + i).to_string(), &(base_volume + i as u64).to_string()))
+            .collect()
+    }
+
+    fn new_format_record() -> String {
+        format!(
+            "{code}{time}{remark}{trend}{match_flag}{limit}{price}{volume}{buy_tick}{buy_limit}{buy_levels}{sell_tick}{sell_limit}{sell_levels}{date}{staff}",
+            code = "002330",
+            time = "090000123456",
+            remark = " ",
+            trend = "+",
+            match_flag = "Y",
+            limit = " ",
+            price = "005850",
+            volume = "00001000",
+            buy_tick = "5",
+            buy_limit = " ",
+            buy_levels = levels_block(58490, 10),
+            sell_tick = "5",
+            sell_limit = " ",
+            sell_levels = levels_block(58510, 20),
+            date = "20240301",
+            staff = "01",
+        )
+    }
+
+    fn old_format_record() -> String {
+        format!(
+            "{code}{time}{remark}{trend}{match_flag}{limit}{price}{volume}{buy_tick}{buy_limit}{buy_levels}{sell_tick}{sell_limit}{sell_levels}{date}{staff}",
+            code = "002330",
+            time = "09000012",
+            remark = " ",
+            trend = "+",
+            match_flag = "Y",
+            limit = " ",
+            price = "005850",
+            volume = "00001000",
+            buy_tick = "5",
+            buy_limit = " ",
+            buy_levels = levels_block(58490, 10),
+            sell_tick = "5",
+            sell_limit = " ",
+            sell_levels = levels_block(58510, 20),
+            date = "20240301",
+            staff = "01",
+        )
+    }
+
+    #[test]
+    fn parse_new_format_decodes_every_field() {
+        let record = new_format_record();
+        assert_eq!(record.len(), 190);
+        let snapshot = parse_new_format(record.as_bytes(), PRICE_SCALE_4);
+
+        assert_eq!(snapshot.securities_code, "002330");
+        assert_eq!(snapshot.display_time_ns, 9 * 3_600_000_000_000 + 123_456_000);
+        assert_eq!(snapshot.display_date, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        assert_eq!(snapshot.remark, ' ');
+        assert_eq!(snapshot.trend_flag, '+');
+        assert_eq!(snapshot.match_flag, 'Y');
+        assert_eq!(snapshot.trade_upper_lower_limit, ' ');
+        assert_eq!(snapshot.trade_price, 5_850);
+        assert_eq!(snapshot.transaction_volume, 1_000);
+        assert_eq!(snapshot.price_scale, PRICE_SCALE_4);
+        assert_eq!(snapshot.buy_tick_size, '5');
+        assert_eq!(snapshot.buy_upper_lower_limit, ' ');
+        assert_eq!(
+            snapshot.buy_levels,
+            [
+                PriceLevel { price: 58490, volume: 10 },
+                PriceLevel { price: 58491, volume: 11 },
+                PriceLevel { price: 58492, volume: 12 },
+                PriceLevel { price: 58493, volume: 13 },
+                PriceLevel { price: 58494, volume: 14 },
+            ]
+        );
+        assert_eq!(snapshot.sell_tick_size, '5');
+        assert_eq!(snapshot.sell_upper_lower_limit, ' ');
+        assert_eq!(
+            snapshot.sell_levels,
+            [
+                PriceLevel { price: 58510, volume: 20 },
+                PriceLevel { price: 58511, volume: 21 },
+                PriceLevel { price: 58512, volume: 22 },
+                PriceLevel { price: 58513, volume: 23 },
+                PriceLevel { price: 58514, volume: 24 },
+            ]
+        );
+        assert_eq!(snapshot.match_staff, "01");
+    }
+
+    #[test]
+    fn parse_old_format_decodes_every_field() {
+        let record = old_format_record();
+        assert_eq!(record.len(), 186);
+        let snapshot = parse_old_format(record.as_bytes(), PRICE_SCALE_2);
+
+        assert_eq!(snapshot.securities_code, "002330");
+        assert_eq!(snapshot.display_time_ns, 9 * 3_600_000_000_000 + 120_000_000);
+        assert_eq!(snapshot.display_date, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        assert_eq!(snapshot.trade_price, 5_850);
+        assert_eq!(snapshot.transaction_volume, 1_000);
+        assert_eq!(snapshot.price_scale, PRICE_SCALE_2);
+        assert_eq!(snapshot.buy_levels[0], PriceLevel { price: 58490, volume: 10 });
+        assert_eq!(snapshot.sell_levels[0], PriceLevel { price: 58510, volume: 20 });
+        assert_eq!(snapshot.match_staff, "01");
+    }
+
+    #[test]
+    fn parse_display_time_ns_decodes_microseconds() {
+        assert_eq!(parse_display_time_ns(b"090000123456"), 9 * 3_600_000_000_000 + 123_456_000);
+    }
+
+    #[test]
+    fn parse_display_time_ns_decodes_centiseconds() {
+        assert_eq!(parse_display_time_ns(b"09000012"), 9 * 3_600_000_000_000 + 120_000_000);
+    }
+
+    #[test]
+    fn parse_price_levels_respects_tick_size() {
+        let block = levels_block(58490, 10);
+        let levels = parse_price_levels(block.as_bytes(), 3);
+
+        assert_eq!(
+            levels,
+            [
+                PriceLevel { price: 58490, volume: 10 },
+                PriceLevel { price: 58491, volume: 11 },
+                PriceLevel { price: 58492, volume: 12 },
+                PriceLevel::default(),
+                PriceLevel::default(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Streaming, per-security end-of-day summary: consumes the parsed snapshot
+//! stream and maintains running aggregates in a single pass, without
+//! buffering the records.
+
+use std::collections::HashMap;
+
+use crate::snapshot::Snapshot;
+
+/// Running aggregates for one security, updated incrementally as each of
+/// its snapshots arrives.
+#[derive(Debug, Clone)]
+pub struct SecuritySummary {
+    pub last_trade_price: i64,
+    pub session_high: Option<i64>,
+    pub session_low: Option<i64>,
+    pub cumulative_volume: u64,
+    sum_price_volume: u128,
+    pub best_bid: i64,
+    pub best_bid_volume: u64,
+    pub best_ask: i64,
+    pub best_ask_volume: u64,
+}
+
+impl SecuritySummary {
+    fn new(snapshot: &Snapshot) -> SecuritySummary {
+        let mut summary = SecuritySummary {
+            last_trade_price: 0,
+            session_high: None,
+            session_low: None,
+            cumulative_volume: 0,
+            sum_price_volume: 0,
+            best_bid: 0,
+            best_bid_volume: 0,
+            best_ask: 0,
+            best_ask_volume: 0,
+        };
+        summary.update(snapshot);
+        summary
+    }
+
+    fn update(&mut self, snapshot: &Snapshot) {
+        if snapshot.trade_price > 0 {
+            self.last_trade_price = snapshot.trade_price;
+            self.session_high = Some(
+                self.session_high
+                    .map_or(snapshot.trade_price, |high| high.max(snapshot.trade_price)),
+            );
+            self.session_low = Some(
+                self.session_low
+                    .map_or(snapshot.trade_price, |low| low.min(snapshot.trade_price)),
+            );
+        }
+        if snapshot.transaction_volume > 0 {
+            self.cumulative_volume += snapshot.transaction_volume;
+            self.sum_price_volume +=
+                snapshot.trade_price as u128 * snapshot.transaction_volume as u128;
+        }
+
+        let top_bid = snapshot.buy_levels[0];
+        if top_bid.price > 0 {
+            self.best_bid = top_bid.price;
+            self.best_bid_volume = top_bid.volume;
+        }
+        let top_ask = snapshot.sell_levels[0];
+        if top_ask.price > 0 {
+            self.best_ask = top_ask.price;
+            self.best_ask_volume = top_ask.volume;
+        }
+    }
+
+    /// Volume-weighted average price over the session so far, scaled like
+    /// `Snapshot::trade_price` (divide by the security's `Snapshot::price_scale`
+    /// for the decimal price).
+    pub fn vwap(&self) -> f64 {
+        if self.cumulative_volume == 0 {
+            0.0
+        } else {
+            self.sum_price_volume as f64 / self.cumulative_volume as f64
+        }
+    }
+}
+
+/// Streaming, single-pass per-security aggregator, keyed by
+/// `securities_code`.
+#[derive(Debug, Default)]
+pub struct Summarizer {
+    by_security: HashMap<String, SecuritySummary>,
+}
+
+impl Summarizer {
+    pub fn new() -> Summarizer {
+        Summarizer::default()
+    }
+
+    /// Fold one snapshot into its security's running aggregates.
+    pub fn update(&mut self, snapshot: &Snapshot) {
+        self.by_security
+            .entry(snapshot.securities_code.clone())
+            .and_modify(|summary| summary.update(snapshot))
+            .or_insert_with(|| SecuritySummary::new(snapshot));
+    }
+
+    /// End-of-file summary rows, sorted by securities code.
+    pub fn summaries(&self) -> Vec<(&str, &SecuritySummary)> {
+        let mut rows: Vec<_> = self
+            .by_security
+            .iter()
+            .map(|(code, summary)| (code.as_str(), summary))
+            .collect();
+        rows.sort_by_key(|(code, _)| *code);
+        rows
+    }
+}